@@ -1,110 +1,441 @@
 // rk3588_gpio.rs (最終驗證版)
-// 該驅動程式用於控制 RK3588 SoC 的單一 GPIO 引腳。
+// 該驅動程式用於控制 RK3588 SoC 的 GPIO 引腳。
 
-use core::ptr::write_volatile;
+use core::ptr::{read_volatile, write_volatile};
 
 // --- 已確認的硬體位址與偏移 ---
 
-/// RK3588 GPIO Bank 1 的記憶體基底 L位址。
-/// 來源: RK3588 TRM Part1, Chapter 20 GPIO & Linux DTS file.
-const GPIO1_BASE: usize = 0xfec20000; // 在DTS aliases中gpio1對應的地址是/pinctrl/gpio@fec20000，故使用此地址
+/// 各 GPIO Bank 的記憶體基底位址 (GPIO0 ~ GPIO4)。
+/// 來源: RK3588 TRM Part1, Chapter 20 GPIO & Linux DTS aliases。
+/// DTS 中 gpio1 對應的節點為 /pinctrl/gpio@fec20000，其餘依序排列。
+const GPIO_BANK_BASE: [usize; 5] = [
+    0xfd8a0000, // GPIO0
+    0xfec20000, // GPIO1
+    0xfec30000, // GPIO2
+    0xfec40000, // GPIO3
+    0xfec50000, // GPIO4
+];
+
+/// GPIO 資料暫存器 (低 16 位)。控制 A0-B7 引腳的輸出電平。
+/// 來源: RK3588 TRM Part1, Page 1470, "Registers Summary".
+const GPIO_SWPORT_DR_L_OFFSET: usize = 0x0000;
 
 /// GPIO 資料暫存器 (高 16 位)。控制 C0-D7 引腳的輸出電平。
 /// 來源: RK3588 TRM Part1, Page 1470, "Registers Summary".
 const GPIO_SWPORT_DR_H_OFFSET: usize = 0x0004;
 
+/// GPIO 方向暫存器 (低 16 位)。控制 A0-B7 引腳的輸入/輸出模式。
+/// 來源: RK3588 TRM Part1, Page 1470, "Registers Summary".
+const GPIO_SWPORT_DDR_L_OFFSET: usize = 0x0008;
+
 /// GPIO 方向暫存器 (高 16 位)。控制 C0-D7 引腳的輸入/輸出模式。
 /// 來源: RK3588 TRM Part1, Page 1470, "Registers Summary".
 const GPIO_SWPORT_DDR_H_OFFSET: usize = 0x000C;
 
-/// BUS_IOC (I/O Controller) 的記憶體基底 L位址。
-/// 來源: rk3588-orangepi-5-plus.dts, syscon@fd5f0000 node.
-const BUS_IOC_BASE: usize = 0xFD5F0000;
+// 以下 GPIO-v2 中斷暫存器均為 _L/_H 成對、相隔 8 位元組 (此處記錄 _L 基底，
+// write_masked_bit 在引腳落在 16-31 時 +4 取 _H)；偏移取自 Linux 的 DW APB
+// GPIO v2 映射，可與驅動程式對照核對。
+// 來源: Linux drivers/gpio/gpio-rockchip.c, GPIO_TYPE_V2 暫存器定義。
+
+/// GPIO 中斷致能暫存器 (_L 基底，_H 為 +4)。1 -> 致能該引腳中斷。
+/// 沿用 _L/_H 與高 16 位寫入遮罩慣例。
+/// 來源: gpio-rockchip.c, `GPIO_INT_EN_V2` = 0x10。
+const GPIO_INT_EN_OFFSET: usize = 0x0010;
+
+/// GPIO 中斷遮罩暫存器 (_L 基底，_H 為 +4)。1 -> 遮蔽 (屏蔽) 該引腳中斷。
+/// 來源: gpio-rockchip.c, `GPIO_INT_MASK_V2` = 0x18。
+const GPIO_INT_MASK_OFFSET: usize = 0x0018;
+
+/// GPIO 中斷類型暫存器 (_L 基底，_H 為 +4)。1 -> 邊緣觸發，0 -> 準位觸發。
+/// 來源: gpio-rockchip.c, `GPIO_INT_TYPE_V2` = 0x20。
+const GPIO_INT_TYPE_OFFSET: usize = 0x0020;
+
+/// GPIO 中斷極性暫存器 (_L 基底，_H 為 +4)。1 -> 上升緣/高準位，0 -> 下降緣/低準位。
+/// 來源: gpio-rockchip.c, `GPIO_INT_POLARITY_V2` = 0x28。
+const GPIO_INT_POLARITY_OFFSET: usize = 0x0028;
+
+/// GPIO 雙邊緣觸發暫存器 (_L 基底，_H 為 +4)。1 -> 上升與下降緣皆觸發。
+/// 來源: gpio-rockchip.c, `GPIO_INT_BOTHEDGE_V2` = 0x30。
+const GPIO_INT_BOTHEDGE_OFFSET: usize = 0x0030;
+
+/// GPIO 中斷狀態暫存器 (唯讀，完整 32 位元，無寫入遮罩)。
+/// 來源: gpio-rockchip.c, `GPIO_INT_STATUS_V2` = 0x50。
+const GPIO_INT_STATUS_OFFSET: usize = 0x0050;
+
+/// GPIO 中斷清除 (EOI) 暫存器 (_L 基底，_H 為 +4)。寫 1 清除對應引腳的中斷，
+/// 與其他中斷暫存器相同採 _L/_H 分割與高 16 位寫入遮罩 (`_H` 變體的存在即證明
+/// 其為遮罩式，而非整段 32 位元)。
+/// 來源: gpio-rockchip.c, `GPIO_PORTS_EOI_V2` = 0x60 (經 `rockchip_gpio_writel_bit` 遮罩)。
+const GPIO_PORT_EOI_OFFSET: usize = 0x0060;
+
+/// GPIO 外部埠暫存器。讀取引腳當前的輸入電平 (輸入模式下為外部訊號，
+/// 輸出模式下為回讀的鎖存值)，為完整 32 位元，無寫入遮罩。
+/// 來源: gpio-rockchip.c, `GPIO_EXT_PORT_V2` = 0x70。
+const GPIO_EXT_PORT_OFFSET: usize = 0x0070;
+
+/// GPIO 去抖動 (debounce) 暫存器 (_L 基底，_H 為 +4)。1 -> 對該輸入引腳啟用
+/// 硬體去抖動，過濾機械按鍵/開關的毛刺。沿用 _L/_H 與高 16 位寫入遮罩慣例。
+/// 來源: gpio-rockchip.c, `GPIO_DEBOUNCE_V2` = 0x38。
+const GPIO_DEBOUNCE_OFFSET: usize = 0x0038;
+
+/// GPIO 去抖動時脈分頻致能暫存器 (_L 基底，_H 為 +4，per-pin)。
+/// 1 -> 該引腳使用分頻後的去抖動時脈。沿用 _L/_H 與高 16 位寫入遮罩慣例。
+/// 來源: gpio-rockchip.c, `GPIO_DBCLK_DIV_EN_V2` = 0x40。
+const GPIO_DBCLK_DIV_EN_OFFSET: usize = 0x0040;
+
+/// GPIO 去抖動時脈分頻係數暫存器 (整個 bank 共用，完整 32 位元，無寫入遮罩)。
+/// 來源: gpio-rockchip.c, `GPIO_DBCLK_DIV_CON_V2` = 0x48。
+const GPIO_DBCLK_DIV_CON_OFFSET: usize = 0x0048;
+
+/// BUS_IOC (I/O Controller) 的記憶體基底位址，負責 GPIO1 ~ GPIO4 的 IOMUX。
+/// 來源: rk3588.dtsi, bus_ioc@fd5f8000 node.
+const BUS_IOC_BASE: usize = 0xFD5F8000;
+
+/// PMU_IOC 的記憶體基底位址，負責 GPIO0 的 IOMUX。
+/// 來源: rk3588.dtsi, pmu1_ioc@fd5f0000 node.
+const PMU_IOC_BASE: usize = 0xFD5F0000;
+
+/// BUS_IOC 中每個 bank 的 IOMUX 區塊大小。
+/// GPIO1C_IOMUX_SEL_H 位於 0x0034 (來源: RK3588 TRM, Page 984)；每個 bank 有
+/// 4 個 group，每個 group 各有 _L/_H 兩個 32 位元暫存器，共佔 0x20 個位元組，
+/// 故 GPIO{bank} 的區塊起點 = bank * 0x20。
+const BUS_IOC_BANK_STRIDE: usize = 0x20;
+
+/// 整個 IOC syscon 的根基底 (0xfd5f0000)。其下以固定偏移切分為多個子區塊：
+/// PMU1/PMU2 與各 VCCIO IOC。與只負責 IOMUX 選擇的 BUS_IOC 不同，pull 與
+/// drive-strength 暫存器並不在 BUS_IOC，而是依每個引腳所屬的 IO 電壓域分散在
+/// PMU 與 VCCIO 子區塊中，因此這裡必須從 IOC 根基底、而非 `iomux_base` 起算。
+/// 來源: pinctrl-rockchip.c, `RK3588_PMU1_IOC_BASE`/`RK3588_VCCIO*_IOC_BASE`.
+const IOC_BASE: usize = 0xFD5F0000;
+
+/// IOC 子區塊相對於 [`IOC_BASE`] 的偏移。
+/// 來源: pinctrl-rockchip.c, `RK3588_*_IOC_BASE`.
+const PMU1_IOC_OFFSET: usize = 0x0000;
+const PMU2_IOC_OFFSET: usize = 0x4000;
+const VCCIO1_4_IOC_OFFSET: usize = 0x9000;
+const VCCIO3_5_IOC_OFFSET: usize = 0xA000;
+const VCCIO6_IOC_OFFSET: usize = 0xC000;
+
+/// 每個 bank 的 pull 暫存器所在 IOC 子區塊偏移，以及該 bank 在子區塊內的基底偏移。
+/// 子區塊依 bank 的主要 IO 電壓域選定；bank0 的 A/B 群組在 PMU1、C/D 群組在 PMU2
+/// (於 [`GpioPin::pull_reg_addr`] 中特判)。bank 內基底取自驅動程式的
+/// `RK3588_PULL_GPIO{0..4}_OFFSET` (0x20/0x30/0x40/0x50/0x60)。
+/// 來源: pinctrl-rockchip.c, `rk3588_calc_pull_reg_and_bit`.
+const RK3588_PULL_BANK: [(usize, usize); 5] = [
+    (PMU1_IOC_OFFSET, 0x0020),
+    (VCCIO1_4_IOC_OFFSET, 0x0030),
+    (VCCIO3_5_IOC_OFFSET, 0x0040),
+    (VCCIO3_5_IOC_OFFSET, 0x0050),
+    (VCCIO6_IOC_OFFSET, 0x0060),
+];
+
+/// 每個 bank 的 drive-strength 暫存器所在 IOC 子區塊偏移與 bank 內基底偏移。
+/// 規則同 [`RK3588_PULL_BANK`]；bank 內基底取自驅動程式的
+/// `RK3588_DRV_GPIO{0..4}_OFFSET` (0x10/0x20/0x40/0x60/0x80)。
+/// 來源: pinctrl-rockchip.c, `rk3588_calc_drv_reg_and_bit`.
+const RK3588_DRV_BANK: [(usize, usize); 5] = [
+    (PMU1_IOC_OFFSET, 0x0010),
+    (VCCIO1_4_IOC_OFFSET, 0x0020),
+    (VCCIO3_5_IOC_OFFSET, 0x0040),
+    (VCCIO3_5_IOC_OFFSET, 0x0060),
+    (VCCIO6_IOC_OFFSET, 0x0080),
+];
+
+
+/// 引腳的上下拉設定。
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Pull {
+    /// 停用上下拉 (高阻態)。
+    None,
+    /// 上拉。
+    Up,
+    /// 下拉。
+    Down,
+}
+
+impl Pull {
+    /// RK3588 IOC pull 暫存器中 2 位元欄位的編碼。
+    /// 來源: RK3588 TRM (IOC), "GPIO{bank}{group}_P_*".
+    fn bits(self) -> u32 {
+        match self {
+            Pull::None => 0b00,
+            Pull::Up => 0b01,
+            Pull::Down => 0b10,
+        }
+    }
+}
+
+
+/// 中斷的觸發條件。
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// 上升緣觸發。
+    RisingEdge,
+    /// 下降緣觸發。
+    FallingEdge,
+    /// 雙邊緣觸發 (上升與下降)。
+    BothEdges,
+    /// 高準位觸發。
+    HighLevel,
+    /// 低準位觸發。
+    LowLevel,
+}
+
+
+/// 實體位址到虛擬位址的映射。
+///
+/// `GpioPin` 在每次存取暫存器前都會透過此 trait 將實體 MMIO 位址轉為可存取的
+/// 位址。真正的裸機環境使用 [`IdentityMap`] (原樣回傳)，而在 MMU 或核心下執行
+/// 時則可換上會把實體 MMIO `ioremap` 到虛擬位址的實作，避免一開啟分頁就發生
+/// 頁面錯誤。
+pub trait RegisterMap {
+    /// 將實體位址 `phys` 轉換為實際存取用的位址。
+    fn phys_to_virt(&self, phys: usize) -> usize;
+}
 
-/// GPIO1C 組引腳的 IOMUX 功能選擇暫存器 (高位)。
-/// 來源: RK3588 TRM (BUS_IOC), Page 984, "Registers Summary".
-const BUS_IOC_GPIO1C_IOMUX_SEL_H_OFFSET: usize = 0x0034;
+/// 預設的恆等映射，直接回傳實體位址，適用於無 MMU 的裸機環境。
+#[derive(Clone, Copy, Default)]
+pub struct IdentityMap;
+
+impl RegisterMap for IdentityMap {
+    fn phys_to_virt(&self, phys: usize) -> usize {
+        phys
+    }
+}
 
 
 /// 代表一個 GPIO 引腳的驅動程式結構。
-pub struct GpioPin {
-    pin_num_global: u8, // 全局引腳編號 (0-31), 例如 C4 是 20
+///
+/// 型別參數 `M` 為位址映射方式，預設為裸機用的 [`IdentityMap`]。
+pub struct GpioPin<M: RegisterMap = IdentityMap> {
+    bank: u8,  // GPIO bank 編號 (0-4)
+    pin: u8,   // bank 內的本地引腳編號 (0-31), 例如 GPIO1_C4 是 20
     gpio_base: usize,
-    bus_ioc_base: usize,
+    iomux_base: usize,
+    map: M,
 }
 
-impl GpioPin {
+impl GpioPin<IdentityMap> {
+    /// 建立一個代表任意 RK3588 引腳的新實例 (使用恆等位址映射)。
+    ///
+    /// 參數沿用 Linux gpiochip 模型：`bank` 為控制器編號 (0-4)，`group` 為該
+    /// bank 內的 A/B/C/D 群組 (0-3)，`index` 為群組內的引腳 (0-7)；對應的 sysfs
+    /// 全局編號為 `bank * 32 + group * 8 + index`。建構子會依 bank 選出正確的
+    /// 記憶體基底，並判斷 GPIO0 使用 PMU_IOC、其餘使用 BUS_IOC；後續的暫存器
+    /// 存取再依本地引腳落在 0-15 或 16-31 自動選擇 `_L` 或 `_H` 變體。
+    pub fn new(bank: u8, group: u8, index: u8) -> Self {
+        Self::with_map(bank, group, index, IdentityMap)
+    }
+
     /// 建立一個代表 Orange Pi 5 Plus 板載 LED (GPIO1_C4) 的新實例。
     pub fn new_led() -> Self {
-        Self {
-            pin_num_global: 20, // GPIO1_C4: C 組是第 3 組 (A=0, B=1, C=2), C4 是該組第 4 個引腳。
-                               // 全局索引 = 8(A組) + 8(B組) + 4 = 20
-            gpio_base: GPIO1_BASE,
-            bus_ioc_base: BUS_IOC_BASE,
-        }
+        // GPIO1_C4: C 組是第 3 組 (A=0, B=1, C=2), C4 在該組內的索引為 4。
+        // bank 內本地索引 = 8(A組) + 8(B組) + 4 = 20 (sysfs 全局編號為 32 + 20 = 52)
+        Self::new(1, 2, 4)
     }
+}
 
-    // TODO: 這個函數僅用於測試目的。稍後可移除。
-    /// 為測試環境建立一個使用偽造基地址的 GpioPin 實例。
-    pub fn new_led_for_test(gpio_base: usize, bus_ioc_base: usize) -> Self {
+impl<M: RegisterMap> GpioPin<M> {
+    /// 以自訂的位址映射 `map` 建立一個引腳實例。
+    ///
+    /// 供在 MMU 或核心下執行、需要把實體 MMIO `ioremap` 到虛擬位址的環境使用；
+    /// 也是單元測試以假暫存器緩衝區取代真實位址的原則性做法。
+    pub fn with_map(bank: u8, group: u8, index: u8, map: M) -> Self {
         Self {
-            pin_num_global: 20,
-            gpio_base,
-            bus_ioc_base,
+            bank,
+            pin: group * 8 + index,
+            gpio_base: GPIO_BANK_BASE[bank as usize],
+            iomux_base: if bank == 0 { PMU_IOC_BASE } else { BUS_IOC_BASE },
+            map,
         }
     }
 
+    /// 經由位址映射計算 GPIO bank 暫存器的存取位址。
+    fn gpio_reg(&self, offset: usize) -> usize {
+        self.map.phys_to_virt(self.gpio_base + offset)
+    }
+
+    /// 經由位址映射計算 IOMUX 暫存器 (相對於 `iomux_base`) 的存取位址。
+    /// pull 與 drive-strength 暫存器不經此函式，而是自 IOC 根基底計算。
+    fn iomux_reg(&self, offset: usize) -> usize {
+        self.map.phys_to_virt(self.iomux_base + offset)
+    }
+
+    /// 計算本引腳 IOMUX_SEL 暫存器的偏移。
+    ///
+    /// IOMUX 以 4 個位元為一個引腳，每個 group 由 `_L` (pin 0-3) 與 `_H` (pin 4-7)
+    /// 兩個 32 位元暫存器涵蓋，每個暫存器佔 4 位元組。GPIO0 由 PMU_IOC 從偏移 0
+    /// 起算，其餘 bank 則位於 BUS_IOC 的 `bank * 0x20` 區塊內；因 bank0 乘上
+    /// stride 即為 0，兩者可共用同一算式。
+    fn iomux_sel_offset(&self) -> usize {
+        let group = (self.pin / 8) as usize;
+        let index = (self.pin % 8) as usize;
+        let bank_off = self.bank as usize * BUS_IOC_BANK_STRIDE;
+        // group 佔 8 位元組 (_L + _H)，index >= 4 落入 _H (再 +4)。
+        bank_off + group * 8 + (index / 4) * 4
+    }
+
     /// 將引腳的硬體功能設定為 GPIO 模式。
     /// 這是操作 GPIO 的第一步，必須先執行。
     /// 資訊來源: RK3588 TRM (BUS_IOC), Page 984, 990.
     pub fn set_function_gpio(&self) {
-        // 計算 IOMUX 控制暫存器的完整記憶體位址
-        let iomux_reg_addr = self.bus_ioc_base + BUS_IOC_GPIO1C_IOMUX_SEL_H_OFFSET;
-        
-        // GPIO1_C4 對應 gpio1c4_sel, 位於該暫存器的 bits [3:0]。
-        // 每個引腳的 IOMUX 設定佔用 4 個位元。
-        // C4 在 (C4, C5, C6, C7) 這半組中的索引是 0。
-        let pin_bit_offset = (self.pin_num_global % 4) * 4;
+        // 計算 IOMUX 控制暫存器的完整記憶體位址 (經位址映射)
+        let iomux_reg_addr = self.iomux_reg(self.iomux_sel_offset());
+
+        // 每個引腳的 IOMUX 設定佔用 4 個位元；在所屬的 _L/_H 半組中，
+        // 引腳的索引為 pin % 4。
+        let pin_bit_offset = (self.pin % 4) * 4;
 
         // 使用 GRF/IOC 的寫入遮罩機制: 高 16 位為遮罩，低 16 位為數值。
-        // 1. 準備寫入遮罩：我們要修改 bits [3:0]，所以遮罩是 0b1111。
+        // 1. 準備寫入遮罩：我們要修改該引腳的 bits [3:0]，所以遮罩是 0b1111。
         let write_mask = 0b1111 << (16 + pin_bit_offset);
         // 2. 準備數值：GPIO 功能對應的值是 0。
         let value = 0b0000 << pin_bit_offset;
-        
+
         unsafe {
             // 寫入暫存器以改變引腳功能
             write_volatile(iomux_reg_addr as *mut u32, write_mask | value);
         }
     }
 
+    /// 經由位址映射計算本引腳 pull 暫存器的存取位址。
+    ///
+    /// pull 暫存器從 IOC 根基底起算：先選出本 bank 的 IOC 子區塊 (bank0 的 C/D
+    /// 群組改用 PMU2) 與 bank 內基底，再以每暫存器 8 個引腳 (每引腳 2 位元) 加上
+    /// `(pin / 8) * 4` 的群組偏移。
+    fn pull_reg_addr(&self) -> usize {
+        let (sub, base) = RK3588_PULL_BANK[self.bank as usize];
+        let sub = if self.bank == 0 && self.pin >= 16 {
+            PMU2_IOC_OFFSET
+        } else {
+            sub
+        };
+        let reg = sub + base + (self.pin as usize / 8) * 4;
+        self.map.phys_to_virt(IOC_BASE + reg)
+    }
+
+    /// 經由位址映射計算本引腳 drive-strength 暫存器的存取位址。
+    ///
+    /// 規則同 [`Self::pull_reg_addr`]，但每暫存器僅 4 個引腳 (每引腳 4 位元)，
+    /// 故群組偏移為 `(pin / 4) * 4`。
+    fn ds_reg_addr(&self) -> usize {
+        let (sub, base) = RK3588_DRV_BANK[self.bank as usize];
+        let sub = if self.bank == 0 && self.pin >= 16 {
+            PMU2_IOC_OFFSET
+        } else {
+            sub
+        };
+        let reg = sub + base + (self.pin as usize / 4) * 4;
+        self.map.phys_to_virt(IOC_BASE + reg)
+    }
+
+    /// 設定引腳的上下拉。
+    ///
+    /// 上下拉設定位於 IOC 的 `GPIO{bank}{group}_P_*` 暫存器，每個引腳佔 2 個
+    /// 位元、每個 32 位元暫存器容納 8 個引腳，並沿用高 16 位寫入遮罩慣例。這些
+    /// 暫存器依引腳的 IO 電壓域分散於 PMU 與 VCCIO 子區塊 (並不在 BUS_IOC)，
+    /// 位址由 [`Self::pull_reg_addr`] 自 IOC 根基底計算。
+    /// 資訊來源: pinctrl-rockchip.c, `rk3588_calc_pull_reg_and_bit`.
+    pub fn set_pull(&self, pull: Pull) {
+        let reg_addr = self.pull_reg_addr();
+
+        // 每個引腳 2 個位元，暫存器內位置為 (pin % 8) * 2。
+        let bit_offset = (self.pin as usize % 8) * 2;
+        let mask = 0b11 << (16 + bit_offset);
+        let value = pull.bits() << bit_offset;
+
+        unsafe {
+            write_volatile(reg_addr as *mut u32, mask | value);
+        }
+    }
+
+    /// 設定引腳的驅動強度。
+    ///
+    /// 驅動強度位於 IOC 的 `GPIO{bank}{group}_DS_*` 暫存器，每個引腳佔 4 個
+    /// 位元、每個 32 位元暫存器容納 4 個引腳 (其餘高 16 位為寫入遮罩)，故 pin 4-7
+    /// 會落在同一 group 的下一個暫存器。`level` 僅取低 4 位。這些暫存器同樣依
+    /// IO 電壓域分散於 PMU 與 VCCIO 子區塊，位址由 [`Self::ds_reg_addr`] 計算。
+    /// 資訊來源: pinctrl-rockchip.c, `rk3588_calc_drv_reg_and_bit`.
+    pub fn set_drive_strength(&self, level: u8) {
+        let reg_addr = self.ds_reg_addr();
+
+        // 每個引腳 4 個位元，暫存器內位置為 (pin % 4) * 4。
+        let bit_offset = (self.pin as usize % 4) * 4;
+        let mask = 0b1111 << (16 + bit_offset);
+        let value = ((level as u32) & 0b1111) << bit_offset;
+
+        unsafe {
+            write_volatile(reg_addr as *mut u32, mask | value);
+        }
+    }
+
     /// 將引腳的方向設定為輸出 (Output) 模式。
     /// 資訊來源: RK3588 TRM Part1, Page 1470, 1471.
     pub fn set_as_output(&self) {
-        // 引腳 20 屬於高 16 位 (16-31)，因此使用 _DDR_H 暫存器。
-        let ddr_reg_addr = self.gpio_base + GPIO_SWPORT_DDR_H_OFFSET;
-        // 在高 16 位組內，引腳 20 的本地索引是 4 (20 - 16 = 4)。
-        let local_pin_num = self.pin_num_global - 16;
+        // 依引腳落在低 16 位 (0-15) 或高 16 位 (16-31) 選擇 _DDR_L / _DDR_H 暫存器。
+        let (ddr_offset, local_pin_num) = if self.pin < 16 {
+            (GPIO_SWPORT_DDR_L_OFFSET, self.pin)
+        } else {
+            (GPIO_SWPORT_DDR_H_OFFSET, self.pin - 16)
+        };
+        let ddr_reg_addr = self.gpio_reg(ddr_offset);
 
         // 使用 GPIO 的寫入遮罩機制。
         // 1. 準備遮罩，致能對 local_pin_num 的寫入。
         let mask = 1 << (16 + local_pin_num);
         // 2. 準備數值，將 local_pin_num 對應位設為 1 (Output)。
         let value = 1 << local_pin_num;
-        
+
+        unsafe {
+            write_volatile(ddr_reg_addr as *mut u32, mask | value);
+        }
+    }
+
+    /// 將引腳的方向設定為輸入 (Input) 模式。
+    /// 清除 DDR 中對應的位元 (0 -> Input)，其餘遵循與 `set_as_output` 相同的
+    /// _L/_H 與寫入遮罩慣例。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470, 1471.
+    pub fn set_as_input(&self) {
+        let (ddr_offset, local_pin_num) = if self.pin < 16 {
+            (GPIO_SWPORT_DDR_L_OFFSET, self.pin)
+        } else {
+            (GPIO_SWPORT_DDR_H_OFFSET, self.pin - 16)
+        };
+        let ddr_reg_addr = self.gpio_reg(ddr_offset);
+
+        // 致能對 local_pin_num 的寫入，數值位保持 0 (Input)。
+        let mask = 1 << (16 + local_pin_num);
+        let value = 0 << local_pin_num;
+
         unsafe {
             write_volatile(ddr_reg_addr as *mut u32, mask | value);
         }
     }
 
+    /// 讀取引腳的當前電平。
+    ///
+    /// 從本 bank 的 `GPIO_EXT_PORT` 暫存器讀取並取出本引腳對應的位元；在輸入
+    /// 模式下即為外部訊號電平，在輸出模式下為回讀的鎖存值。回傳 `true` 表示
+    /// 高電平。此暫存器為唯讀的完整 32 位元，不使用寫入遮罩。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470.
+    pub fn read(&self) -> bool {
+        let ext_port_addr = self.gpio_reg(GPIO_EXT_PORT_OFFSET);
+        let value = unsafe { read_volatile(ext_port_addr as *const u32) };
+        (value >> self.pin) & 1 == 1
+    }
+
     /// 設置引腳為高電平 (點亮 LED)。
     pub fn set_high(&self) {
-        let dr_reg_addr = self.gpio_base + GPIO_SWPORT_DR_H_OFFSET;
-        let local_pin_num = self.pin_num_global - 16;
-        
+        let (dr_offset, local_pin_num) = if self.pin < 16 {
+            (GPIO_SWPORT_DR_L_OFFSET, self.pin)
+        } else {
+            (GPIO_SWPORT_DR_H_OFFSET, self.pin - 16)
+        };
+        let dr_reg_addr = self.gpio_reg(dr_offset);
+
         let mask = 1 << (16 + local_pin_num);
         let value = 1 << local_pin_num; // 1 -> High
-        
+
         unsafe {
             write_volatile(dr_reg_addr as *mut u32, mask | value);
         }
@@ -112,14 +443,166 @@ impl GpioPin {
 
     /// 設置引腳為低電平 (熄滅 LED)。
     pub fn set_low(&self) {
-        let dr_reg_addr = self.gpio_base + GPIO_SWPORT_DR_H_OFFSET;
-        let local_pin_num = self.pin_num_global - 16;
+        let (dr_offset, local_pin_num) = if self.pin < 16 {
+            (GPIO_SWPORT_DR_L_OFFSET, self.pin)
+        } else {
+            (GPIO_SWPORT_DR_H_OFFSET, self.pin - 16)
+        };
+        let dr_reg_addr = self.gpio_reg(dr_offset);
 
         let mask = 1 << (16 + local_pin_num);
         let value = 0 << local_pin_num; // 0 -> Low
-        
+
         unsafe {
             write_volatile(dr_reg_addr as *mut u32, mask | value);
         }
     }
-}
\ No newline at end of file
+
+    /// 對一個採 _L/_H 分割且使用高 16 位寫入遮罩的暫存器寫入本引腳的單一位元。
+    /// `base_offset` 為 _L 暫存器偏移，引腳落在 16-31 時改用 +4 的 _H 暫存器。
+    fn write_masked_bit(&self, base_offset: usize, bit: bool) {
+        let (offset, local_pin_num) = if self.pin < 16 {
+            (base_offset, self.pin)
+        } else {
+            (base_offset + 4, self.pin - 16)
+        };
+        let reg_addr = self.gpio_reg(offset);
+
+        let mask = 1 << (16 + local_pin_num);
+        let value = (bit as u32) << local_pin_num;
+
+        unsafe {
+            write_volatile(reg_addr as *mut u32, mask | value);
+        }
+    }
+
+    /// 致能本引腳的中斷並設定觸發條件。
+    ///
+    /// 依 `trigger` 設定中斷類型 (邊緣/準位)、極性與雙邊緣，接著解除遮罩並致能
+    /// 中斷。各暫存器沿用 _L/_H 分割與高 16 位寫入遮罩慣例。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470.
+    pub fn enable_interrupt(&self, trigger: Trigger) {
+        let is_edge = !matches!(trigger, Trigger::HighLevel | Trigger::LowLevel);
+        let both_edges = matches!(trigger, Trigger::BothEdges);
+        // 極性: 上升緣/高準位為 1，下降緣/低準位為 0；雙邊緣時極性不具意義，取 1。
+        let polarity_high = matches!(
+            trigger,
+            Trigger::RisingEdge | Trigger::HighLevel | Trigger::BothEdges
+        );
+
+        self.write_masked_bit(GPIO_INT_TYPE_OFFSET, is_edge);
+        self.write_masked_bit(GPIO_INT_POLARITY_OFFSET, polarity_high);
+        self.write_masked_bit(GPIO_INT_BOTHEDGE_OFFSET, both_edges);
+        // 解除遮罩 (0 = 不遮蔽) 並致能中斷。
+        self.write_masked_bit(GPIO_INT_MASK_OFFSET, false);
+        self.write_masked_bit(GPIO_INT_EN_OFFSET, true);
+    }
+
+    /// 停用本引腳的中斷。
+    /// 清除中斷致能位並同時遮蔽該引腳。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470.
+    pub fn disable_interrupt(&self) {
+        self.write_masked_bit(GPIO_INT_EN_OFFSET, false);
+        self.write_masked_bit(GPIO_INT_MASK_OFFSET, true);
+    }
+
+    /// 查詢本引腳是否有待處理的中斷。
+    /// 讀取完整 32 位元的 `GPIO_INT_STATUS` 並取出對應位元。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470.
+    pub fn is_pending(&self) -> bool {
+        let status_addr = self.gpio_reg(GPIO_INT_STATUS_OFFSET);
+        let value = unsafe { read_volatile(status_addr as *const u32) };
+        (value >> self.pin) & 1 == 1
+    }
+
+    /// 清除本引腳待處理的中斷。
+    /// 對 `GPIO_PORT_EOI` 寫 1 清除對應位元；與其他中斷暫存器相同，為 _L/_H
+    /// 成對並採高 16 位寫入遮罩，故走 `write_masked_bit` 路徑 (而非整段 32 位元
+    /// 直寫，否則 0-15 的引腳會因未設遮罩位而被硬體忽略、16-31 則會寫錯暫存器)。
+    /// 資訊來源: gpio-rockchip.c, `rockchip_gpio_writel_bit()` 對 EOI 套用遮罩。
+    pub fn clear_pending(&self) {
+        self.write_masked_bit(GPIO_PORT_EOI_OFFSET, true);
+    }
+
+    /// 啟用本引腳的硬體去抖動。
+    ///
+    /// 在 `GPIO_DEBOUNCE` 中設定本引腳對應的位元，讓控制器過濾輸入上的毛刺；
+    /// 對作為中斷或感測來源的機械按鍵/開關特別重要。沿用 _L/_H 分割與高 16 位
+    /// 寫入遮罩慣例。去抖動所用的取樣時脈可再以 [`Self::set_debounce_divider`]
+    /// 調整。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470.
+    pub fn enable_debounce(&self) {
+        self.write_masked_bit(GPIO_DEBOUNCE_OFFSET, true);
+    }
+
+    /// 停用本引腳的硬體去抖動。
+    /// 清除 `GPIO_DEBOUNCE` 中本引腳對應的位元。
+    /// 資訊來源: RK3588 TRM Part1, Page 1470.
+    pub fn disable_debounce(&self) {
+        self.write_masked_bit(GPIO_DEBOUNCE_OFFSET, false);
+    }
+
+    /// 設定去抖動取樣時脈的分頻係數，並對本引腳啟用分頻時脈。
+    ///
+    /// `GPIO_DBCLK_DIV_CON` 為整個 bank 共用的完整 32 位元分頻值；
+    /// `GPIO_DBCLK_DIV_EN` 則為 per-pin 的致能位 (採 _L/_H 分割與高 16 位寫入
+    /// 遮罩)，故此處依 `self.pin` 透過 `write_masked_bit` 致能對應引腳，而非
+    /// 硬寫 bit 0。較大的分頻值會降低取樣頻率、延長過濾窗口。
+    /// 資訊來源: gpio-rockchip.c, `GPIO_DBCLK_DIV_CON_V2` / `GPIO_DBCLK_DIV_EN_V2`.
+    pub fn set_debounce_divider(&self, div: u32) {
+        let con_addr = self.gpio_reg(GPIO_DBCLK_DIV_CON_OFFSET);
+        unsafe {
+            // DIV_CON 為整個 bank 共用的完整 32 位元分頻值。
+            write_volatile(con_addr as *mut u32, div);
+        }
+        // DIV_EN 為 per-pin 致能位，對本引腳啟用分頻時脈。
+        self.write_masked_bit(GPIO_DBCLK_DIV_EN_OFFSET, true);
+    }
+}
+
+// --- embedded-hal 數位介面 ---
+//
+// 在 `embedded-hal` feature 啟用時，為 `GpioPin` 實作 embedded-hal 的數位
+// trait，讓既有的 driver crate (LED 矩陣、移位暫存器、bit-bang 匯流排等) 能
+// 直接驅動 RK3588 引腳。inherent 方法仍保留；trait 只是薄薄地轉呼叫它們，
+// 並以 `Infallible` 作為錯誤型別 (寫入遮罩存取不會失敗)。
+#[cfg(feature = "embedded-hal")]
+impl<M: RegisterMap> embedded_hal::digital::ErrorType for GpioPin<M> {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<M: RegisterMap> embedded_hal::digital::OutputPin for GpioPin<M> {
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        GpioPin::set_high(self);
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        GpioPin::set_low(self);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<M: RegisterMap> embedded_hal::digital::StatefulOutputPin for GpioPin<M> {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        // 由 EXT_PORT 回讀輸出鎖存值。
+        Ok(self.read())
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.read())
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<M: RegisterMap> embedded_hal::digital::InputPin for GpioPin<M> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.read())
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.read())
+    }
+}
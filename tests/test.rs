@@ -12,9 +12,29 @@ extern crate bare_test;
 mod tests {
     use bare_test::println;
     // Use the correct crate name as defined in Cargo.toml
-    use gpio_rk3588_fresh_led::rk3588_gpio::GpioPin;
+    use gpio_rk3588_fresh_led::rk3588_gpio::{GpioPin, RegisterMap};
     use log::info;
 
+    /// 測試用的位址映射：把 GPIO1 與 BUS_IOC 的實體基底分別重導到堆上的假暫存器
+    /// 緩衝區，取代舊的 `new_led_for_test` hack，改以正式的 `RegisterMap` 機制。
+    struct FakeMap {
+        gpio_phys: usize,
+        gpio_virt: usize,
+        ioc_phys: usize,
+        ioc_virt: usize,
+    }
+
+    impl RegisterMap for FakeMap {
+        fn phys_to_virt(&self, phys: usize) -> usize {
+            // GPIO1 區塊的偏移在 0x80 以內，其餘一律視為 BUS_IOC 區塊。
+            if phys.wrapping_sub(self.gpio_phys) < 0x80 {
+                self.gpio_virt + (phys - self.gpio_phys)
+            } else {
+                self.ioc_virt + (phys - self.ioc_phys)
+            }
+        }
+    }
+
     #[test]
     fn it_works() {
         info!("This is a test log message.");
@@ -43,8 +63,15 @@ mod tests {
         let gpio_base = fake_gpio_regs.as_ptr() as usize;
         let ioc_base = fake_ioc_regs.as_ptr() as usize;
 
-        // 1. 使用測試專用的構造函數和偽造的基地址建立一個 GpioPin 實例。
-        let led_pin = GpioPin::new_led_for_test(gpio_base, ioc_base);
+        // 1. 以 FakeMap 把 GPIO1_C4 的實體基底重導到假暫存器，建立一個 GpioPin 實例。
+        //    (實體基底取自驅動程式：GPIO1 = 0xfec20000, BUS_IOC = 0xfd5f8000)
+        let map = FakeMap {
+            gpio_phys: 0xfec20000,
+            gpio_virt: gpio_base,
+            ioc_phys: 0xfd5f8000,
+            ioc_virt: ioc_base,
+        };
+        let led_pin = GpioPin::with_map(1, 2, 4, map);
         println!("- GpioPin created for test with fake bases: gpio=0x{:x}, ioc=0x{:x}", gpio_base, ioc_base);
 
         // 2. 將引腳功能設定為 GPIO。